@@ -0,0 +1,87 @@
+use crate::terminal::Rgb;
+use crate::Highlighting;
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Deserialize, Default)]
+struct ColorschemeConfig {
+	colors: ColorsConfig,
+}
+
+#[derive(Deserialize, Default)]
+struct ColorsConfig {
+	number: Option<[u8; 3]>,
+	string: Option<[u8; 3]>,
+	comment: Option<[u8; 3]>,
+	keyword: Option<[u8; 3]>,
+	match_fg: Option<[u8; 3]>,
+	match_bg: Option<[u8; 3]>,
+}
+
+pub struct Colorscheme {
+	number: Rgb,
+	string: Rgb,
+	comment: Rgb,
+	keyword: Rgb,
+	match_fg: Rgb,
+	match_bg: Rgb,
+}
+
+impl Default for Colorscheme {
+	fn default() -> Self {
+		Self {
+			number: Rgb(209, 154, 102),
+			string: Rgb(152, 195, 121),
+			comment: Rgb(92, 99, 112),
+			keyword: Rgb(198, 120, 221),
+			match_fg: Rgb(0, 0, 0),
+			match_bg: Rgb(229, 192, 123),
+		}
+	}
+}
+
+impl Colorscheme {
+	/*
+		Load a colorscheme from a TOML config file, falling back to defaults for
+		any color that's missing or when the file can't be read or parsed
+	*/
+	pub fn load(path: &str) -> Self {
+		let default = Self::default();
+		let Ok(contents) = fs::read_to_string(path) else {
+			return default;
+		};
+		let Ok(config) = toml::from_str::<ColorschemeConfig>(&contents) else {
+			return default;
+		};
+		Self {
+			number: config.colors.number.map_or(default.number, rgb),
+			string: config.colors.string.map_or(default.string, rgb),
+			comment: config.colors.comment.map_or(default.comment, rgb),
+			keyword: config.colors.keyword.map_or(default.keyword, rgb),
+			match_fg: config.colors.match_fg.map_or(default.match_fg, rgb),
+			match_bg: config.colors.match_bg.map_or(default.match_bg, rgb),
+		}
+	}
+
+	/*
+		Foreground color for a highlighting kind, or None to reset to the terminal default
+	*/
+	pub fn fg_color(&self, highlighting: Highlighting) -> Option<Rgb> {
+		match highlighting {
+			Highlighting::None => None,
+			Highlighting::Number => Some(self.number),
+			Highlighting::String => Some(self.string),
+			Highlighting::Comment => Some(self.comment),
+			Highlighting::Keyword => Some(self.keyword),
+			Highlighting::Match => Some(self.match_fg),
+		}
+	}
+
+	pub fn match_bg_color(&self) -> Rgb {
+		self.match_bg
+	}
+}
+
+fn rgb(components: [u8; 3]) -> Rgb {
+	Rgb(components[0], components[1], components[2])
+}
@@ -0,0 +1,192 @@
+use crate::Position;
+use crate::Row;
+use crate::SearchDirection;
+use std::fs;
+use std::io::Write;
+
+const RUST_KEYWORDS: &[&str] = &[
+	"as", "break", "const", "continue", "crate", "else", "enum", "extern", "fn", "for", "if",
+	"impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self",
+	"Self", "static", "struct", "super", "trait", "true", "false", "type", "unsafe", "use",
+	"where", "while",
+];
+
+#[derive(Default)]
+pub struct Document {
+	rows: Vec<Row>,
+	pub file_name: Option<String>,
+	dirty: bool,
+}
+
+impl Document {
+	pub fn open(filename: &str) -> Result<Self, std::io::Error> {
+		let contents = fs::read_to_string(filename)?;
+		let mut rows = Vec::new();
+		for value in contents.lines() {
+			rows.push(Row::from(value));
+		}
+		let mut document = Self {
+			rows,
+			file_name: Some(filename.to_string()),
+			dirty: false,
+		};
+		document.highlight();
+		Ok(document)
+	}
+
+	/*
+		Recompute syntax highlighting for every row, using a keyword set chosen by file extension
+	*/
+	pub fn highlight(&mut self) {
+		let keywords = self.keywords();
+		for row in &mut self.rows {
+			row.highlight(keywords);
+		}
+	}
+
+	fn keywords(&self) -> &'static [&'static str] {
+		match self.file_name.as_deref().and_then(|name| name.rsplit('.').next()) {
+			Some("rs") => RUST_KEYWORDS,
+			_ => &[],
+		}
+	}
+
+	/*
+		Recompute syntax highlighting for a single row, so edits don't pay for a
+		full-document rehighlight on every keystroke
+	*/
+	fn highlight_row(&mut self, index: usize) {
+		let keywords = self.keywords();
+		if let Some(row) = self.rows.get_mut(index) {
+			row.highlight(keywords);
+		}
+	}
+
+	pub fn row(&self, index: usize) -> Option<&Row> {
+		self.rows.get(index)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.rows.is_empty()
+	}
+
+	pub fn len(&self) -> usize {
+		self.rows.len()
+	}
+
+	pub fn is_dirty(&self) -> bool {
+		self.dirty
+	}
+
+	/*
+		Insert a character at `at`, splitting the row on newline
+	*/
+	pub fn insert(&mut self, at: &Position, c: char) {
+		if at.y > self.rows.len() {
+			return;
+		}
+		self.dirty = true;
+		if c == '\n' {
+			self.insert_newline(at);
+			self.highlight_row(at.y);
+			self.highlight_row(at.y + 1);
+			return;
+		}
+		if at.y == self.rows.len() {
+			let mut row = Row::default();
+			row.insert(0, c);
+			self.rows.push(row);
+		} else if let Some(row) = self.rows.get_mut(at.y) {
+			row.insert(at.x, c);
+		}
+		self.highlight_row(at.y);
+	}
+
+	/*
+		Delete the character at `at`, joining with the next row when at the line end
+	*/
+	pub fn delete(&mut self, at: &Position) {
+		let len = self.rows.len();
+		if at.y >= len {
+			return;
+		}
+		self.dirty = true;
+		if at.x == self.rows[at.y].len() && at.y + 1 < len {
+			let next_row = self.rows.remove(at.y + 1);
+			let row = &mut self.rows[at.y];
+			row.append(&next_row);
+		} else {
+			let row = &mut self.rows[at.y];
+			row.delete(at.x);
+		}
+		self.highlight_row(at.y);
+	}
+
+	fn insert_newline(&mut self, at: &Position) {
+		if at.y > self.rows.len() {
+			return;
+		}
+		if at.y == self.rows.len() {
+			self.rows.push(Row::default());
+			return;
+		}
+		let new_row = self.rows[at.y].split(at.x);
+		self.rows.insert(at.y + 1, new_row);
+	}
+
+	/*
+		Find `query` scanning from `after`, wrapping around the document. `advance` skips past
+		`after` itself, for moving to the next/previous match; pass `false` to search inclusively
+		from `after`, e.g. while the query is still being typed.
+	*/
+	pub fn find(
+		&self,
+		query: &str,
+		after: &Position,
+		direction: SearchDirection,
+		advance: bool,
+	) -> Option<Position> {
+		if query.is_empty() || self.rows.is_empty() {
+			return None;
+		}
+		let num_rows = self.rows.len();
+		let mut y = after.y;
+		// Forward advancing starts past the current position so it doesn't re-find the match
+		// the cursor is already on; Backward already excludes it via `graphemes[..at]`.
+		let mut x = match direction {
+			SearchDirection::Forward if advance => after.x.saturating_add(1),
+			_ => after.x,
+		};
+
+		for _ in 0..=num_rows {
+			if let Some(row) = self.rows.get(y) {
+				if let Some(found_x) = row.find(query, x, direction) {
+					return Some(Position { x: found_x, y });
+				}
+			}
+			match direction {
+				SearchDirection::Forward => {
+					y = if y + 1 >= num_rows { 0 } else { y + 1 };
+					x = 0;
+				}
+				SearchDirection::Backward => {
+					y = if y == 0 { num_rows - 1 } else { y - 1 };
+					x = self.rows[y].len();
+				}
+			}
+		}
+		None
+	}
+
+	pub fn save(&mut self) -> Result<(), std::io::Error> {
+		if let Some(file_name) = &self.file_name {
+			let mut file = fs::File::create(file_name)?;
+			for row in &self.rows {
+				file.write_all(&row.as_bytes())?;
+				file.write_all(b"\n")?;
+			}
+			self.dirty = false;
+		}
+		Ok(())
+	}
+}
@@ -1,17 +1,28 @@
+use crate::terminal::Rgb;
+use crate::Colorscheme;
 use crate::Document;
+use crate::Key;
 use crate::Row;
+use crate::SearchDirection;
 use crate::Terminal;
 use std::env;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::time::Instant;
-use termion::color;
-use termion::event::Key;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::time;
+use unicode_segmentation::UnicodeSegmentation;
 
-const STATUS_FG_COLOR: color::Rgb = color::Rgb(63, 63, 63);
-const STATUS_BG_COLOR: color::Rgb = color::Rgb(239, 239, 239);
+const STATUS_FG_COLOR: Rgb = Rgb(63, 63, 63);
+const STATUS_BG_COLOR: Rgb = Rgb(239, 239, 239);
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+const COLORSCHEME_FILE: &str = "rustyvim.toml";
+const TICK_RATE: Duration = Duration::from_millis(200);
+const MESSAGE_LIFETIME: Duration = Duration::from_secs(5);
+const CLOSE_BUTTON: &str = "[X]";
+const SCROLL_STEP: usize = 3;
 
-#[derive(Default)]
+#[derive(Default, Clone, Copy)]
 pub struct Position {
 	pub x: usize,
 	pub y: usize,
@@ -31,26 +42,108 @@ impl StatusMessage {
 	}
 }
 
-pub struct Editor {
+#[derive(PartialEq, Clone, Copy)]
+enum Mode {
+	Normal,
+	Insert,
+}
+
+/*
+	Mutable editor state, shared behind a mutex so the render path doesn't have to block on
+	the input path, and so background tasks (e.g. a future autosave) can reach it too
+*/
+struct EditorState {
 	should_quit: bool,
-	terminal: Terminal,
 	cursor_position: Position,
 	offset: Position,
 	document: Document,
-	status_message: StatusMessage,
+	// Dismissible notifications (errors, save results, ...), most recent last
+	status_messages: Vec<StatusMessage>,
+	// The "prompt: input-so-far" line shown while `prompt()` is reading a line, if active
+	prompt_line: Option<String>,
+	mode: Mode,
+	highlighted_match: Option<(Position, usize)>,
+}
+
+impl EditorState {
+	/*
+		Display-column position of the cursor on its current row, accounting for wide graphemes
+	*/
+	fn cursor_display_x(&self) -> usize {
+		self.document
+			.row(self.cursor_position.y)
+			.map_or(self.cursor_position.x, |row| {
+				row.width_until(self.cursor_position.x)
+			})
+	}
+
+	/*
+		Queue a status message, collapsing a repeat of the current one into a timer refresh
+		instead of stacking a duplicate entry
+	*/
+	fn push_status_message(&mut self, text: String) {
+		if let Some(top) = self.status_messages.last_mut() {
+			if top.text == text {
+				top.time = Instant::now();
+				return;
+			}
+		}
+		self.status_messages.push(StatusMessage::from(text));
+	}
+
+	/*
+		Drop the current message once it's outlived its display window, along with any
+		expired messages queued underneath it; those are older, so expiry clears the queue
+	*/
+	fn dismiss_expired_status_messages(&mut self) {
+		while self
+			.status_messages
+			.last()
+			.is_some_and(|message| Instant::now() - message.time >= MESSAGE_LIFETIME)
+		{
+			self.status_messages.pop();
+		}
+	}
+}
+
+pub struct Editor {
+	terminal: Terminal,
+	state: Arc<Mutex<EditorState>>,
+	colorscheme: Colorscheme,
 }
 
 impl Editor {
-	pub fn run(&mut self) {
+	/*
+		Spawn a blocking key-reader task and drive rendering from an async select loop, so a
+		resize or the message bar's expiry redraws the screen even without a keypress
+	*/
+	pub async fn run(&mut self) {
+		let (tx, mut rx) = mpsc::unbounded_channel::<Key>();
+		tokio::task::spawn_blocking(move || {
+			while let Ok(key) = Terminal::read_key() {
+				if tx.send(key).is_err() {
+					break;
+				}
+			}
+		});
+
+		let mut tick = time::interval(TICK_RATE);
+
 		loop {
 			if let Err(error) = self.refresh_screen() {
 				die(error);
 			}
-			if self.should_quit {
+			if self.state.lock().unwrap().should_quit {
 				break;
 			}
-			if let Err(error) = self.process_keypress() {
-				die(error);
+
+			tokio::select! {
+				Some(key) = rx.recv() => {
+					if let Err(error) = self.process_keypress(key, &mut rx).await {
+						die(error);
+					}
+				}
+				_ = tick.tick() => {}
 			}
 		}
 	}
@@ -61,9 +154,9 @@ impl Editor {
 		let mut initial_status = String::from("Ctrl-Q to quit");
 		let document = if args.len() > 1 {
 			let file_name = &args[1];
-			let doc = Document::open(&file_name);
-			if doc.is_ok() {
-				doc.unwrap()
+			let doc = Document::open(file_name);
+			if let Ok(doc) = doc {
+				doc
 			} else {
 				initial_status = format!("ERROR: Can't open {}", file_name);
 				Document::default()
@@ -72,13 +165,21 @@ impl Editor {
 			Document::default()
 		}; // ; here and not inside ensures doc is never undefined
 
-		Self {
+		let state = EditorState {
 			should_quit: false,
-			terminal: Terminal::default().expect("Failed to init terminal"), 
-			document,	
 			cursor_position: Position::default(),
 			offset: Position::default(),
-			status_message: StatusMessage::from(initial_status),
+			document,
+			status_messages: vec![StatusMessage::from(initial_status)],
+			prompt_line: None,
+			mode: Mode::Normal,
+			highlighted_match: None,
+		};
+
+		Self {
+			terminal: Terminal::default().expect("Failed to init terminal"),
+			state: Arc::new(Mutex::new(state)),
+			colorscheme: Colorscheme::load(COLORSCHEME_FILE),
 		}
 	}
 
@@ -89,16 +190,18 @@ impl Editor {
 		Terminal::cursor_hide();
 		Terminal::cursor_position(&Position::default());
 
-		if self.should_quit {
+		let mut state = self.state.lock().unwrap();
+		state.dismiss_expired_status_messages();
+		if state.should_quit {
 			println!("Goodbye.\r");
 			Terminal::clear_screen();
 		} else {
-			self.draw_rows();
-			self.draw_status_bar();
-			self.draw_message_bar();
+			self.draw_rows(&state);
+			self.draw_status_bar(&state);
+			self.draw_message_bar(&state);
 			Terminal::cursor_position(&Position {
-				x: self.cursor_position.x.saturating_sub(self.offset.x),
-				y: self.cursor_position.y.saturating_sub(self.offset.y),
+				x: state.cursor_display_x().saturating_sub(state.offset.x),
+				y: state.cursor_position.y.saturating_sub(state.offset.y),
 			});
 		}
 
@@ -107,20 +210,79 @@ impl Editor {
 	}
 
 	/*
-		Read optional key input
+		Handle one key already read from the input task, prompting via `rx` for save/search
 	*/
-	fn process_keypress(&mut self) -> Result<(), std::io::Error> {
-		let pressed_key = Terminal::read_key()?;
+	async fn process_keypress(
+		&mut self,
+		pressed_key: Key,
+		rx: &mut UnboundedReceiver<Key>,
+	) -> Result<(), std::io::Error> {
+		let mode = self.state.lock().unwrap().mode;
 		match pressed_key {
-			Key::Ctrl('q') => self.should_quit = true,
-			Key::Up 
-			| Key:: Down
-			| Key::Left 
-			| Key::Right 
+			Key::Ctrl('q') => self.state.lock().unwrap().should_quit = true,
+			Key::Ctrl('s') => self.save(rx).await,
+			Key::Ctrl('f') => self.search(rx).await,
+			Key::Char('i') if mode == Mode::Normal => {
+				self.state.lock().unwrap().mode = Mode::Insert;
+			}
+			Key::Esc => {
+				let mut state = self.state.lock().unwrap();
+				if state.mode == Mode::Insert {
+					state.mode = Mode::Normal;
+				}
+			}
+			Key::Char(c) => {
+				let move_right = {
+					let mut state = self.state.lock().unwrap();
+					if state.mode != Mode::Insert {
+						false
+					} else {
+						let cursor_position = state.cursor_position;
+						state.document.insert(&cursor_position, c);
+						if c == '\n' {
+							state.cursor_position.y = state.cursor_position.y.saturating_add(1);
+							state.cursor_position.x = 0;
+							false
+						} else {
+							true
+						}
+					}
+				};
+				if move_right {
+					self.move_cursor(Key::Right);
+				}
+			}
+			Key::Backspace => {
+				let should_delete = {
+					let state = self.state.lock().unwrap();
+					state.mode == Mode::Insert
+						&& (state.cursor_position.x > 0 || state.cursor_position.y > 0)
+				};
+				if should_delete {
+					self.move_cursor(Key::Left);
+					let mut state = self.state.lock().unwrap();
+					let cursor_position = state.cursor_position;
+					state.document.delete(&cursor_position);
+				}
+			}
+			Key::Delete => {
+				let mut state = self.state.lock().unwrap();
+				if state.mode == Mode::Insert {
+					let cursor_position = state.cursor_position;
+					state.document.delete(&cursor_position);
+				}
+			}
+			Key::Up
+			| Key::Down
+			| Key::Left
+			| Key::Right
 			| Key::PageUp
 			| Key::PageDown
-			| Key:: End
-			| Key::Home => self.move_cursor(pressed_key),
+			| Key::End
+			| Key::Home
+			| Key::ScrollUp
+			| Key::ScrollDown => self.move_cursor(pressed_key),
+			Key::MouseDown(column, row) => self.handle_mouse_down(column, row),
 			_ => (),
 		}
 
@@ -128,14 +290,172 @@ impl Editor {
 		Ok(())
 	}
 
-	/* 
+	/*
+		Map a left-click to a cursor position in the text area, or to dismissing the current
+		message if it landed on the message bar's close button
+	*/
+	fn handle_mouse_down(&mut self, column: usize, row: usize) {
+		let total_height = self.terminal.size().height as usize;
+		let mut state = self.state.lock().unwrap();
+
+		if row == total_height.saturating_sub(1) && column >= self.close_button_column() {
+			state.status_messages.pop();
+			return;
+		}
+
+		let text_height = total_height.saturating_sub(1 + self.message_bar_height(&state));
+		if row < text_height {
+			let doc_row = row + state.offset.y;
+			if doc_row < state.document.len() {
+				let display_x = column + state.offset.x;
+				let grapheme_x = state
+					.document
+					.row(doc_row)
+					.map_or(0, |r| r.index_for_width(display_x));
+				state.cursor_position = Position {
+					x: grapheme_x,
+					y: doc_row,
+				};
+			}
+		}
+	}
+
+	/*
+		Save the document, prompting for a file name in the message bar when one isn't set
+	*/
+	async fn save(&mut self, rx: &mut UnboundedReceiver<Key>) {
+		let file_name = self.state.lock().unwrap().document.file_name.clone();
+		if file_name.is_none() {
+			let new_name = self
+				.prompt(rx, "Save as: ", |_, _, _| {})
+				.await
+				.unwrap_or(None);
+			if new_name.is_none() {
+				self.state
+					.lock()
+					.unwrap()
+					.push_status_message("Save aborted.".to_string());
+				return;
+			}
+			self.state.lock().unwrap().document.file_name = new_name;
+		}
+
+		let result = self.state.lock().unwrap().document.save();
+		let mut state = self.state.lock().unwrap();
+		if result.is_ok() {
+			state.push_status_message("File saved successfully.".to_string());
+		} else {
+			state.push_status_message("Error writing file!".to_string());
+		}
+	}
+
+	/*
+		Search the document incrementally, restoring position/offset on Esc
+	*/
+	async fn search(&mut self, rx: &mut UnboundedReceiver<Key>) {
+		let (old_position, old_offset) = {
+			let state = self.state.lock().unwrap();
+			(state.cursor_position, state.offset)
+		};
+		let mut direction = SearchDirection::Forward;
+		// The origin for incremental typing stays fixed so refining the query re-searches from
+		// where the search started; navigating with Up/Down/Left/Right advances from the last match.
+		let mut last_match = old_position;
+
+		let query = self
+			.prompt(
+				rx,
+				"Search (Esc to cancel, Up/Down to navigate): ",
+				|editor, key, query| {
+					let advance = matches!(key, Key::Up | Key::Down | Key::Left | Key::Right);
+					direction = match key {
+						Key::Up | Key::Left => SearchDirection::Backward,
+						_ => SearchDirection::Forward,
+					};
+					let search_from = if advance { last_match } else { old_position };
+					let found = {
+						let state = editor.state.lock().unwrap();
+						state.document.find(query, &search_from, direction, advance)
+					};
+					if let Some(position) = found {
+						last_match = position;
+						{
+							let mut state = editor.state.lock().unwrap();
+							state.highlighted_match = Some((position, query.graphemes(true).count()));
+							state.cursor_position = position;
+						}
+						editor.scroll();
+					} else if query.is_empty() {
+						editor.state.lock().unwrap().highlighted_match = None;
+					}
+				},
+			)
+			.await
+			.unwrap_or(None);
+
+		if query.is_none() {
+			let mut state = self.state.lock().unwrap();
+			state.cursor_position = old_position;
+			state.offset = old_offset;
+		}
+		self.state.lock().unwrap().highlighted_match = None;
+	}
+
+	/*
+		Read a line of input from the message bar, calling `callback` on every keystroke; Esc
+		cancels. Keys come from `rx` rather than `Terminal::read_key()` directly, since the
+		input task is the only reader of stdin.
+	*/
+	async fn prompt<C>(
+		&mut self,
+		rx: &mut UnboundedReceiver<Key>,
+		prompt: &str,
+		mut callback: C,
+	) -> Result<Option<String>, std::io::Error>
+	where
+		C: FnMut(&mut Self, Key, &str),
+	{
+		let mut result = String::new();
+		loop {
+			self.state.lock().unwrap().prompt_line = Some(format!("{}{}", prompt, result));
+			self.refresh_screen()?;
+			let key = match rx.recv().await {
+				Some(key) => key,
+				None => break,
+			};
+			match key {
+				Key::Backspace => {
+					result.pop();
+				}
+				Key::Char('\n') => break,
+				Key::Char(c) if !c.is_control() => result.push(c),
+				Key::Esc => {
+					result.truncate(0);
+					callback(self, key, &result);
+					break;
+				}
+				_ => (),
+			}
+			callback(self, key, &result);
+		}
+		self.state.lock().unwrap().prompt_line = None;
+		if result.is_empty() {
+			return Ok(None);
+		}
+		Ok(Some(result))
+	}
+
+	/*
 		Add scroll bump to position
 	*/
 	fn scroll(&mut self) {
-		let Position { x, y } = self.cursor_position;
 		let width = self.terminal.size().width as usize;
-		let height = self.terminal.size().height as usize;
-		let mut offset = &mut self.offset;
+		let total_height = self.terminal.size().height as usize;
+		let mut state = self.state.lock().unwrap();
+		let height = total_height.saturating_sub(1 + self.message_bar_height(&state));
+		let y = state.cursor_position.y;
+		let x = state.cursor_display_x();
+		let offset = &mut state.offset;
 
 		if y < offset.y {
 			offset.y = y;
@@ -150,30 +470,28 @@ impl Editor {
 	}
 
 	/*
-		Handle cursor navigation
+		Handle cursor navigation, including the scroll wheel nudging the cursor by a few lines
+		so the final `scroll()` call in `process_keypress` pulls the viewport along with it
 	*/
 	fn move_cursor(&mut self, key: Key) {
-		let Position { mut y, mut x } = self.cursor_position;
-		let height = self.document.len();
-		let mut width = if let Some(row) = self.document.row(y) {
+		let mut state = self.state.lock().unwrap();
+		let Position { mut y, mut x } = state.cursor_position;
+		let height = state.document.len();
+		let mut width = if let Some(row) = state.document.row(y) {
 			row.len()
 		} else {
 			0
 		};
-		
+
 		match key {
 			Key::Up => y = y.saturating_sub(1),
-			Key::Down => {
-				if y < height {
-					y = y.saturating_add(1);
-				}
-			}
+			Key::Down if y < height => y = y.saturating_add(1),
 			Key::Left => {
 				if x > 0 {
 					x -= 1;
 				} else if y > 0 {
 					y -= 1;
-					if let Some(row) = self.document.row(y) {
+					if let Some(row) = state.document.row(y) {
 						x = row.len()
 					} else {
 						x = 0;
@@ -192,11 +510,13 @@ impl Editor {
 			Key::PageDown => y = height,
 			Key::Home => x = 0,
 			Key::End => x = width,
+			Key::ScrollUp => y = y.saturating_sub(SCROLL_STEP),
+			Key::ScrollDown => y = y.saturating_add(SCROLL_STEP).min(height),
 			_ => (),
 		}
 
 		// Snap scrolling to line ends
-		width = if let Some(row) = self.document.row(y) {
+		width = if let Some(row) = state.document.row(y) {
 			row.len()
 		} else {
 			0
@@ -204,8 +524,8 @@ impl Editor {
 		if x > width {
 			x = width;
 		}
-		
-		self.cursor_position = Position { x, y }
+
+		state.cursor_position = Position { x, y };
 	}
 
 	/*
@@ -223,28 +543,37 @@ impl Editor {
 		println!("{}\r", msg);
 	}
 
-	/* 
+	/*
 		Draw document rows
 	*/
-	pub fn draw_row(&self, row: &Row) {
+	fn draw_row(&self, state: &EditorState, row: &Row, row_index: usize) {
 		let width = self.terminal.size().width as usize;
-		let start = self.offset.x;
-		let end = self.offset.x + width;
-		let row = row.render(start, end);
-		println!("{}\r", row)
+		let start = row.index_for_width(state.offset.x);
+		let end = row.index_for_width(state.offset.x + width);
+		let highlight = state.highlighted_match.and_then(|(position, len)| {
+			if position.y == row_index {
+				Some((position.x, position.x + len))
+			} else {
+				None
+			}
+		});
+		row.render(start, end, &self.colorscheme, highlight);
+		println!("\r")
 	}
-	
+
 	/*
-		Draw terminal row features
+		Draw terminal row features, leaving room at the bottom for the status bar and
+		however many lines the message bar currently needs
 	*/
-	fn draw_rows(&self) {
-		let height = self.terminal.size().height;
-		for terminal_row in 0..height {	
+	fn draw_rows(&self, state: &EditorState) {
+		let total_height = self.terminal.size().height as usize;
+		let text_height = total_height.saturating_sub(1 + self.message_bar_height(state));
+		for terminal_row in 0..text_height {
 			Terminal::clear_current_line();
-			let bump = self.offset.y;
-			if let Some(row) = self.document.row(terminal_row as usize + bump) {
-				self.draw_row(row);
-			} else if self.document.is_empty() && terminal_row == height / 3 {
+			let row_index = terminal_row + state.offset.y;
+			if let Some(row) = state.document.row(row_index) {
+				self.draw_row(state, row, row_index);
+			} else if state.document.is_empty() && terminal_row == text_height / 3 {
 				self.draw_welcome_msg();
 			} else {
 				println!("~\r");
@@ -255,31 +584,40 @@ impl Editor {
 	/*
 		Draw colored status bar with info
 	*/
-	fn draw_status_bar(&self) {
+	fn draw_status_bar(&self, state: &EditorState) {
 		let mut status;
 		let width = self.terminal.size().width as usize;
 		let mut file_name = "[No Name]".to_string();
 
 		// File status - name, len
-		if let Some(name) = &self.document.file_name {
-			file_name = name.clone();
-			file_name.truncate(20);
+		if let Some(name) = &state.document.file_name {
+			file_name = Row::truncate_to_width(name, 20);
 		}
-		status = format!("{} - {} lines", file_name, self.document.len());
-		
+		let modified_indicator = if state.document.is_dirty() {
+			" (modified)"
+		} else {
+			""
+		};
+		status = format!(
+			"{} - {} lines{}",
+			file_name,
+			state.document.len(),
+			modified_indicator
+		);
+
 		// Line indicator
 		let line_indicator = format!(
 			"{}/{}",
-			self.cursor_position.y.saturating_add(1),
-			self.document.len()
+			state.cursor_position.y.saturating_add(1),
+			state.document.len()
 		);
 
-		let len = status.len() + line_indicator.len();
+		let len = Row::display_width(&status) + Row::display_width(&line_indicator);
 		if width > len {
 			status.push_str(&" ".repeat(width - len));
-		}		
+		}
 		status = format!("{}{}", status, line_indicator);
-		status.truncate(width);
+		status = Row::truncate_to_width(&status, width);
 
 		Terminal::set_bg_color(STATUS_BG_COLOR);
 		Terminal::set_fg_color(STATUS_FG_COLOR);
@@ -289,20 +627,70 @@ impl Editor {
 	}
 
 	/*
-		Draw bottom status bar
+		Draw the bottom message bar: either the active prompt's input-so-far, or the current
+		queued status message wrapped across as many lines as it needs, with a close button
+		on its last line
 	*/
-	fn draw_message_bar(&self) {
-		Terminal::clear_current_line();
-		let message = &self.status_message;
-		if Instant::now() - message.time < Duration::new(5, 0) {
-			let mut text = message.text.clone();
-			text.truncate(self.terminal.size().width as usize);
-			print!("{}", text);
+	fn draw_message_bar(&self, state: &EditorState) {
+		let width = self.terminal.size().width as usize;
+
+		if let Some(prompt_line) = &state.prompt_line {
+			Terminal::clear_current_line();
+			print!("{}", Row::truncate_to_width(prompt_line, width));
+			return;
 		}
+
+		let Some(message) = state.status_messages.last() else {
+			Terminal::clear_current_line();
+			return;
+		};
+
+		let mut lines = self.wrapped_message_lines(message, width);
+		lines.truncate(self.message_bar_height(state));
+		let last_index = lines.len().saturating_sub(1);
+		for (index, line) in lines.iter().enumerate() {
+			Terminal::clear_current_line();
+			if index == last_index {
+				let padding = self
+					.close_button_column()
+					.saturating_sub(Row::display_width(line));
+				print!("{}{}{}", line, " ".repeat(padding), CLOSE_BUTTON);
+			} else {
+				print!("{}\r\n", line);
+			}
+		}
+	}
+
+	/*
+		Number of terminal rows the message bar currently needs: 1 while idle or prompting,
+		or however many lines the current message wraps to, capped to leave room for the
+		status bar and at least one row of document text
+	*/
+	fn message_bar_height(&self, state: &EditorState) -> usize {
+		if state.prompt_line.is_some() {
+			return 1;
+		}
+		let total_height = self.terminal.size().height as usize;
+		let max_height = total_height.saturating_sub(2).max(1);
+		let width = self.terminal.size().width as usize;
+		state.status_messages.last().map_or(1, |message| {
+			self.wrapped_message_lines(message, width).len().max(1).min(max_height)
+		})
+	}
+
+	fn wrapped_message_lines(&self, message: &StatusMessage, width: usize) -> Vec<String> {
+		Row::wrap_to_width(&message.text, width.saturating_sub(CLOSE_BUTTON.len() + 1))
+	}
+
+	/*
+		Screen column where the message bar's `[X]` close button starts
+	*/
+	fn close_button_column(&self) -> usize {
+		(self.terminal.size().width as usize).saturating_sub(CLOSE_BUTTON.len())
 	}
 }
 
 fn die(e: std::io::Error) {
 	Terminal::clear_screen();
-	panic!(e);
-} 
+	panic!("{}", e);
+}
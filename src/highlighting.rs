@@ -0,0 +1,9 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Highlighting {
+	None,
+	Number,
+	String,
+	Comment,
+	Keyword,
+	Match,
+}
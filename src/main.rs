@@ -0,0 +1,19 @@
+mod colorscheme;
+mod document;
+mod editor;
+mod highlighting;
+mod row;
+mod terminal;
+
+pub use colorscheme::Colorscheme;
+pub use document::Document;
+use editor::Editor;
+pub use editor::Position;
+pub use highlighting::Highlighting;
+pub use row::{Row, SearchDirection};
+pub use terminal::{Key, Terminal};
+
+#[tokio::main]
+async fn main() {
+	Editor::default().run().await;
+}
@@ -0,0 +1,319 @@
+use crate::Colorscheme;
+use crate::Highlighting;
+use crate::Terminal;
+use std::cmp;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+const TAB_WIDTH: usize = 2;
+const CONTROL_PLACEHOLDER: char = '▯';
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum SearchDirection {
+	Forward,
+	Backward,
+}
+
+#[derive(Default)]
+pub struct Row {
+	graphemes: Vec<String>,
+	highlighting: Vec<Highlighting>,
+	len: usize,
+}
+
+impl From<&str> for Row {
+	fn from(slice: &str) -> Self {
+		let graphemes: Vec<String> = slice.graphemes(true).map(String::from).collect();
+		let len = graphemes.len();
+		Self {
+			graphemes,
+			highlighting: vec![Highlighting::None; len],
+			len,
+		}
+	}
+}
+
+impl Row {
+	/*
+		Print the graphemes between `start` and `end`, expanding tabs, substituting
+		a visible placeholder for control characters, and emitting the colorscheme's
+		colors for each grapheme's highlighting through the terminal backend.
+		`highlight_match` is an optional absolute grapheme range painted as a search
+		match instead.
+	*/
+	pub fn render(
+		&self,
+		start: usize,
+		end: usize,
+		colorscheme: &Colorscheme,
+		highlight_match: Option<(usize, usize)>,
+	) {
+		let end = cmp::min(end, self.graphemes.len());
+		let start = cmp::min(start, end);
+		let mut current_highlighting = Highlighting::None;
+		for (offset, grapheme) in self.graphemes[start..end].iter().enumerate() {
+			let index = start + offset;
+			let is_match = highlight_match.is_some_and(|(match_start, match_end)| {
+				index >= match_start && index < match_end
+			});
+			let highlighting = if is_match {
+				Highlighting::Match
+			} else {
+				self.highlighting.get(index).copied().unwrap_or(Highlighting::None)
+			};
+			if highlighting != current_highlighting {
+				current_highlighting = highlighting;
+				match colorscheme.fg_color(highlighting) {
+					Some(color) => Terminal::set_fg_color(color),
+					None => Terminal::reset_fg_color(),
+				}
+			}
+			if is_match {
+				Terminal::set_bg_color(colorscheme.match_bg_color());
+			}
+			if grapheme == "\t" {
+				print!("{}", " ".repeat(TAB_WIDTH));
+			} else if let Some(c) = grapheme.chars().next() {
+				if c.is_control() {
+					print!("{}", CONTROL_PLACEHOLDER);
+				} else {
+					print!("{}", grapheme);
+				}
+			}
+			if is_match {
+				Terminal::reset_bg_color();
+			}
+		}
+		if current_highlighting != Highlighting::None {
+			Terminal::reset_fg_color();
+		}
+	}
+
+	/*
+		Classify graphemes into Highlighting kinds: numbers, quoted strings,
+		`//` line comments, and `keywords` (chosen by the caller from the file extension)
+	*/
+	pub fn highlight(&mut self, keywords: &[&str]) {
+		let mut highlighting = Vec::with_capacity(self.graphemes.len());
+		let mut index = 0;
+		let mut in_string = false;
+
+		while index < self.graphemes.len() {
+			let grapheme = &self.graphemes[index];
+
+			if in_string {
+				highlighting.push(Highlighting::String);
+				if grapheme == "\"" {
+					in_string = false;
+				}
+				index += 1;
+				continue;
+			}
+
+			if grapheme == "\"" {
+				in_string = true;
+				highlighting.push(Highlighting::String);
+				index += 1;
+				continue;
+			}
+
+			if grapheme == "/" && self.graphemes.get(index + 1).is_some_and(|next| next == "/") {
+				highlighting.resize(self.graphemes.len(), Highlighting::Comment);
+				break;
+			}
+
+			if grapheme.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+				highlighting.push(Highlighting::Number);
+				index += 1;
+				continue;
+			}
+
+			if grapheme.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_') {
+				let word_start = index;
+				let mut word = String::new();
+				while let Some(g) = self.graphemes.get(index) {
+					if g.chars().next().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+						word.push_str(g);
+						index += 1;
+					} else {
+						break;
+					}
+				}
+				let kind = if keywords.contains(&word.as_str()) {
+					Highlighting::Keyword
+				} else {
+					Highlighting::None
+				};
+				highlighting.resize(highlighting.len() + (index - word_start), kind);
+				continue;
+			}
+
+			highlighting.push(Highlighting::None);
+			index += 1;
+		}
+
+		self.highlighting = highlighting;
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/*
+		Display width in terminal columns of the first `index` graphemes
+	*/
+	pub fn width_until(&self, index: usize) -> usize {
+		let index = cmp::min(index, self.graphemes.len());
+		self.graphemes[..index]
+			.iter()
+			.map(|g| Self::grapheme_width(g))
+			.sum()
+	}
+
+	/*
+		Grapheme index whose rendered columns first reach or exceed `width`
+	*/
+	pub fn index_for_width(&self, width: usize) -> usize {
+		let mut acc = 0;
+		for (index, grapheme) in self.graphemes.iter().enumerate() {
+			if acc >= width {
+				return index;
+			}
+			acc += Self::grapheme_width(grapheme);
+		}
+		self.graphemes.len()
+	}
+
+	fn grapheme_width(grapheme: &str) -> usize {
+		if grapheme == "\t" {
+			TAB_WIDTH
+		} else {
+			cmp::max(1, grapheme.width())
+		}
+	}
+
+	/*
+		Display width in terminal columns of an arbitrary string, e.g. for status bar layout
+	*/
+	pub fn display_width(text: &str) -> usize {
+		text.graphemes(true).map(Self::grapheme_width).sum()
+	}
+
+	/*
+		Insert a character at the grapheme index `at`, shifting the rest right
+	*/
+	pub fn insert(&mut self, at: usize, c: char) {
+		if at >= self.graphemes.len() {
+			self.graphemes.push(c.to_string());
+		} else {
+			self.graphemes.insert(at, c.to_string());
+		}
+		self.len = self.graphemes.len();
+	}
+
+	/*
+		Remove the grapheme at `at`, shifting the rest left
+	*/
+	pub fn delete(&mut self, at: usize) {
+		if at >= self.graphemes.len() {
+			return;
+		}
+		self.graphemes.remove(at);
+		self.len = self.graphemes.len();
+	}
+
+	pub fn append(&mut self, new: &Self) {
+		self.graphemes.extend(new.graphemes.iter().cloned());
+		self.len = self.graphemes.len();
+	}
+
+	/*
+		Split this row at `at`, keeping the head and returning the tail as a new row
+	*/
+	pub fn split(&mut self, at: usize) -> Self {
+		let at = cmp::min(at, self.graphemes.len());
+		let remainder = self.graphemes.split_off(at);
+		self.len = self.graphemes.len();
+		let len = remainder.len();
+		Self {
+			highlighting: vec![Highlighting::None; len],
+			graphemes: remainder,
+			len,
+		}
+	}
+
+	pub fn as_bytes(&self) -> Vec<u8> {
+		self.graphemes.concat().into_bytes()
+	}
+
+	/*
+		Grapheme index of `query` searching from `at`, forward or backward
+	*/
+	pub fn find(&self, query: &str, at: usize, direction: SearchDirection) -> Option<usize> {
+		if query.is_empty() {
+			return None;
+		}
+		let at = cmp::min(at, self.graphemes.len());
+		match direction {
+			SearchDirection::Forward => {
+				let substring: String = self.graphemes[at..].concat();
+				let byte_index = substring.find(query)?;
+				let offset = substring[..byte_index].graphemes(true).count();
+				Some(at + offset)
+			}
+			SearchDirection::Backward => {
+				let substring: String = self.graphemes[..at].concat();
+				let byte_index = substring.rfind(query)?;
+				let offset = substring[..byte_index].graphemes(true).count();
+				Some(offset)
+			}
+		}
+	}
+
+	/*
+		Truncate arbitrary text to fit within `width` display columns
+	*/
+	pub fn truncate_to_width(text: &str, width: usize) -> String {
+		let mut result = String::new();
+		let mut acc = 0;
+		for grapheme in text.graphemes(true) {
+			let grapheme_width = Self::grapheme_width(grapheme);
+			if acc + grapheme_width > width {
+				break;
+			}
+			acc += grapheme_width;
+			result.push_str(grapheme);
+		}
+		result
+	}
+
+	/*
+		Wrap arbitrary text into lines of at most `width` display columns each,
+		e.g. for displaying a long error across multiple message bar lines
+	*/
+	pub fn wrap_to_width(text: &str, width: usize) -> Vec<String> {
+		let width = cmp::max(width, 1);
+		let mut lines = Vec::new();
+		let mut line = String::new();
+		let mut line_width = 0;
+
+		for grapheme in text.graphemes(true) {
+			let grapheme_width = Self::grapheme_width(grapheme);
+			if line_width + grapheme_width > width && !line.is_empty() {
+				lines.push(line);
+				line = String::new();
+				line_width = 0;
+			}
+			line.push_str(grapheme);
+			line_width += grapheme_width;
+		}
+		if !line.is_empty() || lines.is_empty() {
+			lines.push(line);
+		}
+		lines
+	}
+}
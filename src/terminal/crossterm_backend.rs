@@ -0,0 +1,136 @@
+use super::{Backend, Key, Rgb, Size};
+use crate::Position;
+use crossterm::cursor;
+use crossterm::event::{
+	self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+	MouseButton, MouseEvent, MouseEventKind,
+};
+use crossterm::style::{Color, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{execute, queue};
+use std::io::{self, stdout, Write};
+
+pub struct CrosstermBackend;
+
+impl Backend for CrosstermBackend {
+	fn new() -> Result<Self, std::io::Error> {
+		terminal::enable_raw_mode()?;
+		execute!(stdout(), EnableMouseCapture)?;
+		Ok(Self)
+	}
+
+	// Queried live (not cached) so a terminal resize is picked up on the next redraw
+	fn size(&self) -> Size {
+		let (width, height) = terminal::size().unwrap_or((80, 24));
+		Size { width, height }
+	}
+
+	fn read_key() -> Result<Key, std::io::Error> {
+		loop {
+			match event::read()? {
+				Event::Key(key_event) => return Ok(from_crossterm_key(key_event)),
+				Event::Mouse(mouse_event) => {
+					if let Some(key) = from_crossterm_mouse(mouse_event) {
+						return Ok(key);
+					}
+				}
+				_ => (),
+			}
+		}
+	}
+
+	fn clear_screen() {
+		let _ = execute!(stdout(), Clear(ClearType::All));
+	}
+
+	fn clear_current_line() {
+		let _ = execute!(stdout(), Clear(ClearType::CurrentLine));
+	}
+
+	fn cursor_position(position: &Position) {
+		let x = position.x as u16;
+		let y = position.y as u16;
+		let _ = execute!(stdout(), cursor::MoveTo(x, y));
+	}
+
+	fn cursor_hide() {
+		let _ = execute!(stdout(), cursor::Hide);
+	}
+
+	fn cursor_show() {
+		let _ = execute!(stdout(), cursor::Show);
+	}
+
+	fn flush() -> Result<(), std::io::Error> {
+		io::stdout().flush()
+	}
+
+	fn set_bg_color(color: Rgb) {
+		let _ = queue!(
+			stdout(),
+			SetBackgroundColor(Color::Rgb {
+				r: color.0,
+				g: color.1,
+				b: color.2
+			})
+		);
+	}
+
+	fn reset_bg_color() {
+		let _ = queue!(stdout(), SetBackgroundColor(Color::Reset));
+	}
+
+	fn set_fg_color(color: Rgb) {
+		let _ = queue!(
+			stdout(),
+			SetForegroundColor(Color::Rgb {
+				r: color.0,
+				g: color.1,
+				b: color.2
+			})
+		);
+	}
+
+	fn reset_fg_color() {
+		let _ = queue!(stdout(), SetForegroundColor(Color::Reset));
+	}
+}
+
+impl Drop for CrosstermBackend {
+	fn drop(&mut self) {
+		let _ = execute!(stdout(), DisableMouseCapture);
+		let _ = terminal::disable_raw_mode();
+	}
+}
+
+fn from_crossterm_mouse(event: MouseEvent) -> Option<Key> {
+	match event.kind {
+		MouseEventKind::Down(MouseButton::Left) => {
+			Some(Key::MouseDown(event.column as usize, event.row as usize))
+		}
+		MouseEventKind::ScrollUp => Some(Key::ScrollUp),
+		MouseEventKind::ScrollDown => Some(Key::ScrollDown),
+		_ => None,
+	}
+}
+
+fn from_crossterm_key(key_event: KeyEvent) -> Key {
+	match key_event.code {
+		KeyCode::Char(c) if key_event.modifiers.contains(KeyModifiers::CONTROL) => Key::Ctrl(c),
+		KeyCode::Char(c) if key_event.modifiers.contains(KeyModifiers::ALT) => Key::Alt(c),
+		KeyCode::Char(c) => Key::Char(c),
+		KeyCode::Backspace => Key::Backspace,
+		KeyCode::Delete => Key::Delete,
+		KeyCode::Esc => Key::Esc,
+		KeyCode::Up => Key::Up,
+		KeyCode::Down => Key::Down,
+		KeyCode::Left => Key::Left,
+		KeyCode::Right => Key::Right,
+		KeyCode::Home => Key::Home,
+		KeyCode::End => Key::End,
+		KeyCode::PageUp => Key::PageUp,
+		KeyCode::PageDown => Key::PageDown,
+		KeyCode::Enter => Key::Char('\n'),
+		_ => Key::Other,
+	}
+}
@@ -0,0 +1,25 @@
+/*
+	Backend-neutral key representation so the editor never depends on termion or crossterm types
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+	Char(char),
+	Ctrl(char),
+	Alt(char),
+	Backspace,
+	Delete,
+	Esc,
+	Up,
+	Down,
+	Left,
+	Right,
+	Home,
+	End,
+	PageUp,
+	PageDown,
+	// Left mouse button pressed at 0-indexed (column, row) screen coordinates
+	MouseDown(usize, usize),
+	ScrollUp,
+	ScrollDown,
+	Other,
+}
@@ -0,0 +1,106 @@
+mod key;
+
+#[cfg(any(windows, feature = "crossterm-backend"))]
+mod crossterm_backend;
+#[cfg(not(any(windows, feature = "crossterm-backend")))]
+mod termion_backend;
+
+#[cfg(any(windows, feature = "crossterm-backend"))]
+use crossterm_backend::CrosstermBackend as PlatformBackend;
+#[cfg(not(any(windows, feature = "crossterm-backend")))]
+use termion_backend::TermionBackend as PlatformBackend;
+
+pub use key::Key;
+use crate::Position;
+
+/*
+	Backend-neutral RGB color, independent of termion's or crossterm's color types
+*/
+#[derive(Clone, Copy)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+pub struct Size {
+	pub width: u16,
+	pub height: u16,
+}
+
+/*
+	Everything the editor needs from a terminal. `termion_backend` and `crossterm_backend`
+	each implement this for the platform selected in Cargo.toml / cfg(windows).
+*/
+pub trait Backend: Sized {
+	fn new() -> Result<Self, std::io::Error>;
+	fn size(&self) -> Size;
+	fn read_key() -> Result<Key, std::io::Error>;
+	fn clear_screen();
+	fn clear_current_line();
+	fn cursor_position(position: &Position);
+	fn cursor_hide();
+	fn cursor_show();
+	fn flush() -> Result<(), std::io::Error>;
+	fn set_bg_color(color: Rgb);
+	fn reset_bg_color();
+	fn set_fg_color(color: Rgb);
+	fn reset_fg_color();
+}
+
+pub struct Terminal {
+	backend: PlatformBackend,
+}
+
+impl Terminal {
+	#[allow(clippy::should_implement_trait)]
+	pub fn default() -> Result<Self, std::io::Error> {
+		Ok(Self {
+			backend: PlatformBackend::new()?,
+		})
+	}
+
+	pub fn size(&self) -> Size {
+		self.backend.size()
+	}
+
+	pub fn read_key() -> Result<Key, std::io::Error> {
+		PlatformBackend::read_key()
+	}
+
+	pub fn clear_screen() {
+		PlatformBackend::clear_screen();
+	}
+
+	pub fn clear_current_line() {
+		PlatformBackend::clear_current_line();
+	}
+
+	pub fn cursor_position(position: &Position) {
+		PlatformBackend::cursor_position(position);
+	}
+
+	pub fn cursor_hide() {
+		PlatformBackend::cursor_hide();
+	}
+
+	pub fn cursor_show() {
+		PlatformBackend::cursor_show();
+	}
+
+	pub fn flush() -> Result<(), std::io::Error> {
+		PlatformBackend::flush()
+	}
+
+	pub fn set_bg_color(color: Rgb) {
+		PlatformBackend::set_bg_color(color);
+	}
+
+	pub fn reset_bg_color() {
+		PlatformBackend::reset_bg_color();
+	}
+
+	pub fn set_fg_color(color: Rgb) {
+		PlatformBackend::set_fg_color(color);
+	}
+
+	pub fn reset_fg_color() {
+		PlatformBackend::reset_fg_color();
+	}
+}
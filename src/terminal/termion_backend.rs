@@ -0,0 +1,107 @@
+use super::{Backend, Key, Rgb, Size};
+use crate::Position;
+use std::io::{self, stdout, Write};
+use termion::color;
+use termion::event::{Event, Key as TermionKey, MouseButton, MouseEvent};
+use termion::input::{MouseTerminal, TermRead};
+use termion::raw::{IntoRawMode, RawTerminal};
+
+pub struct TermionBackend {
+	_stdout: MouseTerminal<RawTerminal<std::io::Stdout>>,
+}
+
+impl Backend for TermionBackend {
+	fn new() -> Result<Self, std::io::Error> {
+		Ok(Self {
+			_stdout: MouseTerminal::from(stdout().into_raw_mode()?),
+		})
+	}
+
+	// Queried live (not cached) so a terminal resize is picked up on the next redraw
+	fn size(&self) -> Size {
+		let (width, height) = termion::terminal_size().unwrap_or((80, 24));
+		Size { width, height }
+	}
+
+	fn read_key() -> Result<Key, std::io::Error> {
+		loop {
+			if let Some(event) = io::stdin().lock().events().next() {
+				return event.map(from_termion_event);
+			}
+		}
+	}
+
+	fn clear_screen() {
+		print!("{}", termion::clear::All);
+	}
+
+	fn clear_current_line() {
+		print!("{}", termion::clear::CurrentLine);
+	}
+
+	fn cursor_position(position: &Position) {
+		let x = position.x.saturating_add(1) as u16;
+		let y = position.y.saturating_add(1) as u16;
+		print!("{}", termion::cursor::Goto(x, y));
+	}
+
+	fn cursor_hide() {
+		print!("{}", termion::cursor::Hide);
+	}
+
+	fn cursor_show() {
+		print!("{}", termion::cursor::Show);
+	}
+
+	fn flush() -> Result<(), std::io::Error> {
+		io::stdout().flush()
+	}
+
+	fn set_bg_color(color: Rgb) {
+		print!("{}", color::Bg(color::Rgb(color.0, color.1, color.2)));
+	}
+
+	fn reset_bg_color() {
+		print!("{}", color::Bg(color::Reset));
+	}
+
+	fn set_fg_color(color: Rgb) {
+		print!("{}", color::Fg(color::Rgb(color.0, color.1, color.2)));
+	}
+
+	fn reset_fg_color() {
+		print!("{}", color::Fg(color::Reset));
+	}
+}
+
+fn from_termion_event(event: Event) -> Key {
+	match event {
+		Event::Key(key) => from_termion_key(key),
+		Event::Mouse(MouseEvent::Press(MouseButton::Left, x, y)) => {
+			Key::MouseDown(x.saturating_sub(1) as usize, y.saturating_sub(1) as usize)
+		}
+		Event::Mouse(MouseEvent::Press(MouseButton::WheelUp, ..)) => Key::ScrollUp,
+		Event::Mouse(MouseEvent::Press(MouseButton::WheelDown, ..)) => Key::ScrollDown,
+		_ => Key::Other,
+	}
+}
+
+fn from_termion_key(key: TermionKey) -> Key {
+	match key {
+		TermionKey::Char(c) => Key::Char(c),
+		TermionKey::Ctrl(c) => Key::Ctrl(c),
+		TermionKey::Alt(c) => Key::Alt(c),
+		TermionKey::Backspace => Key::Backspace,
+		TermionKey::Delete => Key::Delete,
+		TermionKey::Esc => Key::Esc,
+		TermionKey::Up => Key::Up,
+		TermionKey::Down => Key::Down,
+		TermionKey::Left => Key::Left,
+		TermionKey::Right => Key::Right,
+		TermionKey::Home => Key::Home,
+		TermionKey::End => Key::End,
+		TermionKey::PageUp => Key::PageUp,
+		TermionKey::PageDown => Key::PageDown,
+		_ => Key::Other,
+	}
+}